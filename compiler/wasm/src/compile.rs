@@ -1,6 +1,6 @@
 use fm::FileManager;
 use gloo_utils::format::JsValueSerdeExt;
-use js_sys::Array;
+use js_sys::{Array, Map};
 use nargo::artifacts::{
     contract::{PreprocessedContract, PreprocessedContractFunction},
     program::PreprocessedProgram,
@@ -10,31 +10,35 @@ use noirc_driver::{
     CompiledContract, CompiledProgram,
 };
 use noirc_frontend::{
-    graph::{CrateGraph, CrateName},
+    graph::{CrateGraph, CrateId, CrateName},
     hir::Context,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     str::FromStr,
 };
 use wasm_bindgen::prelude::*;
 
-use crate::errors::JsCompileError;
+use crate::errors::{to_js_diagnostics, JsCompileError, JsDiagnostic};
 
 const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
 
 #[derive(Deserialize)]
-struct Dependency {
+pub(crate) struct Dependency {
     name: String,
     package_root: PathBuf,
+    #[serde(default)]
+    dependencies: Vec<String>,
 }
 
 #[wasm_bindgen(typescript_custom_section)]
 const DEPENDENCY_TYPE: &'static str = r#"
 export type Dependency = {
     name: string,
-    package_root: string
+    package_root: string,
+    dependencies?: string[]
 }
 "#;
 
@@ -45,110 +49,287 @@ extern "C" {
     pub type DependencyArray;
 }
 
+#[derive(Serialize)]
+pub(crate) struct CompileResult<T: Serialize> {
+    pub(crate) program: T,
+    pub(crate) warnings: Vec<JsDiagnostic>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum BackendLanguage {
+    Plonk { width: usize },
+    R1cs,
+}
+
+impl From<&BackendLanguage> for acvm::Language {
+    fn from(language: &BackendLanguage) -> Self {
+        match language {
+            BackendLanguage::Plonk { width } => acvm::Language::PLONKCSat { width: *width },
+            BackendLanguage::R1cs => acvm::Language::R1CS,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BackendConfig {
+    backend_identifier: String,
+    language: BackendLanguage,
+    /// Names of the ACIR opcodes the backend can consume directly. An empty list falls back to
+    /// ACVM's own `default_is_opcode_supported` predicate for the chosen `language`.
+    #[serde(default)]
+    supported_opcodes: Vec<String>,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        BackendConfig {
+            backend_identifier: BACKEND_IDENTIFIER.to_string(),
+            language: BackendLanguage::Plonk { width: 3 },
+            supported_opcodes: Vec::new(),
+        }
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const BACKEND_CONFIG_TYPE: &'static str = r#"
+export type BackendConfig = {
+    backend_identifier: string,
+    language: { kind: "plonk", width: number } | { kind: "r1cs" },
+    supported_opcodes?: string[]
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "BackendConfig")]
+    pub type JsBackendConfig;
+}
+
+pub(crate) fn parse_backend_config(backend_config: Option<JsBackendConfig>) -> BackendConfig {
+    backend_config
+        .map(|config| {
+            JsValueSerdeExt::into_serde(&config)
+                .expect("Could not deserialize backend config argument")
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Deserialize, Default, Clone, PartialEq, Eq)]
+pub(crate) struct CompileConfig {
+    #[serde(default)]
+    deny_warnings: bool,
+    #[serde(default)]
+    print_acir: bool,
+    #[serde(default)]
+    show_ssa: bool,
+    #[serde(default)]
+    enable_debug_info: bool,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const COMPILE_CONFIG_TYPE: &'static str = r#"
+export type CompileConfig = {
+    deny_warnings?: boolean,
+    print_acir?: boolean,
+    show_ssa?: boolean,
+    enable_debug_info?: boolean
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "CompileConfig")]
+    pub type JsCompileConfig;
+}
+
+impl From<&CompileConfig> for CompileOptions {
+    fn from(config: &CompileConfig) -> Self {
+        CompileOptions {
+            deny_warnings: config.deny_warnings,
+            print_acir: config.print_acir,
+            show_ssa: config.show_ssa,
+            enable_debug_info: config.enable_debug_info,
+            ..CompileOptions::default()
+        }
+    }
+}
+
+pub(crate) fn parse_compile_config(options: Option<JsCompileConfig>) -> CompileConfig {
+    options
+        .map(|options| {
+            JsValueSerdeExt::into_serde(&options)
+                .expect("Could not deserialize compile options argument")
+        })
+        .unwrap_or_default()
+}
+
+/// Builds an opcode-support predicate from a backend's declared capabilities, falling back to
+/// ACVM's default predicate for `language` when no capability set was provided.
+pub(crate) fn make_opcode_support(
+    language: acvm::Language,
+    supported_opcodes: &[String],
+) -> Box<dyn Fn(&acvm::acir::circuit::Opcode) -> bool> {
+    if supported_opcodes.is_empty() {
+        #[allow(deprecated)]
+        return acvm::pwg::default_is_opcode_supported(language);
+    }
+
+    let supported_opcodes: std::collections::HashSet<String> =
+        supported_opcodes.iter().cloned().collect();
+    Box::new(move |opcode| supported_opcodes.contains(&opcode.name()))
+}
+
 #[wasm_bindgen]
 pub fn compile(
     entry_point: String,
     contracts: Option<bool>,
     dependencies: Option<DependencyArray>,
+    file_source_map: Option<Map>,
+    backend_config: Option<JsBackendConfig>,
+    options: Option<JsCompileConfig>,
 ) -> Result<JsValue, JsCompileError> {
     console_error_panic_hook::set_once();
 
     let root = Path::new("/");
-    let fm = FileManager::new(root, Box::new(get_non_stdlib_asset));
+    let overlay = parse_file_source_map(file_source_map);
+    let fm = FileManager::new(root, make_file_resolver(overlay));
     let graph = CrateGraph::default();
     let mut context = Context::new(fm, graph);
 
     let path = Path::new(&entry_point);
     let crate_id = prepare_crate(&mut context, path);
 
-    let dependencies: Vec<Dependency> = dependencies
-        .map(|array| {
-            array
-                .iter()
-                .map(|dep| {
-                    JsValueSerdeExt::into_serde(&dep)
-                        .expect("Could not deserialize dependency argument")
-                })
-                .collect()
-        })
-        .unwrap_or_default();
-    for dependency in dependencies {
-        add_noir_lib(&mut context, dependency);
-    }
+    let dependencies = parse_dependencies(dependencies);
+    let _dependency_crate_ids =
+        add_noir_lib_dependencies(&mut context, dependencies).map_err(JsCompileError::new)?;
 
-    let compile_options = CompileOptions::default();
+    let compile_config = parse_compile_config(options);
+    let compile_options = CompileOptions::from(&compile_config);
 
-    // For now we default to plonk width = 3, though we can add it as a parameter
-    let np_language = acvm::Language::PLONKCSat { width: 3 };
-    #[allow(deprecated)]
-    let is_opcode_supported = acvm::pwg::default_is_opcode_supported(np_language);
+    let backend_config = parse_backend_config(backend_config);
+    let np_language = acvm::Language::from(&backend_config.language);
+    let is_opcode_supported = make_opcode_support(np_language, &backend_config.supported_opcodes);
 
     if contracts.unwrap_or_default() {
-        let compiled_contract = compile_contract(&mut context, crate_id, &compile_options)
-            .map_err(|_| JsCompileError::new("Failed to compile contract".to_string()))?
-            .0;
+        let (compiled_contract, warnings) =
+            compile_contract(&mut context, crate_id, &compile_options).map_err(|diagnostics| {
+                JsCompileError::from_diagnostics(&diagnostics, &context.file_manager)
+            })?;
 
         let optimized_contract =
             nargo::ops::optimize_contract(compiled_contract, np_language, &is_opcode_supported)
                 .expect("Contract optimization failed");
 
-        let preprocessed_contract = preprocess_contract(optimized_contract);
+        let result = CompileResult {
+            program: preprocess_contract(optimized_contract, &backend_config.backend_identifier),
+            warnings: to_js_diagnostics(&warnings, &context.file_manager),
+        };
 
-        Ok(<JsValue as JsValueSerdeExt>::from_serde(&preprocessed_contract).unwrap())
+        Ok(<JsValue as JsValueSerdeExt>::from_serde(&result).unwrap())
     } else {
-        let compiled_program = compile_main(&mut context, crate_id, &compile_options, None, true)
-            .map_err(|_| JsCompileError::new("Failed to compile program".to_string()))?
-            .0;
+        let (compiled_program, warnings) =
+            compile_main(&mut context, crate_id, &compile_options, None, true).map_err(
+                |diagnostics| JsCompileError::from_diagnostics(&diagnostics, &context.file_manager),
+            )?;
 
         let optimized_program =
             nargo::ops::optimize_program(compiled_program, np_language, &is_opcode_supported)
                 .expect("Program optimization failed");
 
-        let preprocessed_program = preprocess_program(optimized_program);
+        let result = CompileResult {
+            program: preprocess_program(optimized_program, &backend_config.backend_identifier),
+            warnings: to_js_diagnostics(&warnings, &context.file_manager),
+        };
 
-        Ok(<JsValue as JsValueSerdeExt>::from_serde(&preprocessed_program).unwrap())
+        Ok(<JsValue as JsValueSerdeExt>::from_serde(&result).unwrap())
     }
 }
 
-fn add_noir_lib(context: &mut Context, library: Dependency) {
-    let path_to_lib = library.package_root.join("src/lib.nr");
-    let library_crate_id = prepare_dependency(context, &path_to_lib);
-
-    let library_name = CrateName::from_str(&library.name).unwrap();
-    add_dep(context, *context.root_crate_id(), library_crate_id, library_name.clone());
-
-    // TODO: Remove this code that attaches every crate to every other crate as a dependency
-    let root_crate_id = context.root_crate_id();
-    let stdlib_crate_id = context.stdlib_crate_id();
-    let other_crate_ids: Vec<_> = context
-        .crate_graph
-        .iter_keys()
-        .filter(|crate_id| {
-            // We don't want to attach this crate to itself or stdlib, nor re-attach it to the root crate
-            crate_id != &library_crate_id
-                && crate_id != root_crate_id
-                && crate_id != stdlib_crate_id
+pub(crate) fn parse_dependencies(dependencies: Option<DependencyArray>) -> Vec<Dependency> {
+    dependencies
+        .map(|array| {
+            array
+                .iter()
+                .map(|dep| {
+                    JsValueSerdeExt::into_serde(&dep)
+                        .expect("Could not deserialize dependency argument")
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Wires up each dependency's own `dependencies` edges rather than attaching every crate to
+/// every other crate. Resolution happens in two passes: first every dependency is prepared so
+/// we have a `CrateId` for each declared name, then the declared edges between those crates (and
+/// from the root crate to every entry in `dependencies`) are added. Doing it in two passes means an
+/// entry can depend on another entry that appears later in the array.
+///
+/// Returns the `CrateId` prepared for each dependency name so that callers (e.g. a persistent
+/// compilation session) can reuse them across calls instead of re-preparing every dependency.
+pub(crate) fn add_noir_lib_dependencies(
+    context: &mut Context,
+    dependencies: Vec<Dependency>,
+) -> Result<HashMap<String, CrateId>, String> {
+    let root_crate_id = *context.root_crate_id();
+
+    let crate_ids: HashMap<String, _> = dependencies
+        .iter()
+        .map(|dependency| {
+            let path_to_lib = dependency.package_root.join("src/lib.nr");
+            (
+                dependency.name.clone(),
+                prepare_dependency(context, &path_to_lib),
+            )
         })
         .collect();
 
-    for crate_id in other_crate_ids {
-        context
-            .crate_graph
-            .add_dep(crate_id, library_name.clone(), library_crate_id)
-            .unwrap_or_else(|_| panic!("ICE: Cyclic error triggered by {library_name} library"));
+    for dependency in &dependencies {
+        let library_crate_id = crate_ids[&dependency.name];
+        let library_name = CrateName::from_str(&dependency.name)
+            .map_err(|_| format!("Invalid dependency name: {}", dependency.name))?;
+
+        add_dep(context, root_crate_id, library_crate_id, library_name);
+
+        for depends_on in &dependency.dependencies {
+            let depends_on_crate_id = *crate_ids.get(depends_on).ok_or_else(|| {
+                format!(
+                    "Dependency '{}' declares a dependency on '{depends_on}' which was not provided",
+                    dependency.name
+                )
+            })?;
+            let depends_on_name = CrateName::from_str(depends_on)
+                .map_err(|_| format!("Invalid dependency name: {depends_on}"))?;
+
+            context
+                .crate_graph
+                .add_dep(library_crate_id, depends_on_name, depends_on_crate_id)
+                .map_err(|_| {
+                    format!(
+                        "Cyclic dependency detected: '{}' -> '{depends_on}'",
+                        dependency.name
+                    )
+                })?;
+        }
     }
+
+    Ok(crate_ids)
 }
 
-fn preprocess_program(program: CompiledProgram) -> PreprocessedProgram {
+pub(crate) fn preprocess_program(program: CompiledProgram, backend: &str) -> PreprocessedProgram {
     PreprocessedProgram {
         hash: program.hash,
-        backend: String::from(BACKEND_IDENTIFIER),
+        backend: backend.to_string(),
         abi: program.abi,
         bytecode: program.circuit,
     }
 }
 
-fn preprocess_contract(contract: CompiledContract) -> PreprocessedContract {
+pub(crate) fn preprocess_contract(
+    contract: CompiledContract,
+    backend: &str,
+) -> PreprocessedContract {
     let preprocessed_functions = contract
         .functions
         .into_iter()
@@ -163,15 +344,46 @@ fn preprocess_contract(contract: CompiledContract) -> PreprocessedContract {
 
     PreprocessedContract {
         name: contract.name,
-        backend: String::from(BACKEND_IDENTIFIER),
+        backend: backend.to_string(),
         functions: preprocessed_functions,
         events: contract.events,
     }
 }
 
+/// Reads `file_source_map`, a JS `Map<string, string>` of path -> contents, into an in-memory
+/// overlay. The overlay is consulted before falling back to the JS `read_file` resolver (or the
+/// wasi filesystem), so editors can compile dirty, unsaved buffers without writing them to disk.
+pub(crate) fn parse_file_source_map(file_source_map: Option<Map>) -> HashMap<PathBuf, String> {
+    let mut overlay = HashMap::new();
+
+    if let Some(map) = file_source_map {
+        map.for_each(&mut |value, key| {
+            if let (Some(key), Some(value)) = (key.as_string(), value.as_string()) {
+                overlay.insert(PathBuf::from(key), value);
+            }
+        });
+    }
+
+    overlay
+}
+
+/// Wraps `get_non_stdlib_asset` with an overlay that takes precedence over it, for use as the
+/// `FileManager`'s file resolver.
+pub(crate) fn make_file_resolver(
+    overlay: HashMap<PathBuf, String>,
+) -> Box<dyn Fn(&Path) -> std::io::Result<String>> {
+    Box::new(move |path_to_file: &Path| {
+        if let Some(contents) = overlay.get(path_to_file) {
+            return Ok(contents.clone());
+        }
+
+        get_non_stdlib_asset(path_to_file)
+    })
+}
+
 cfg_if::cfg_if! {
     if #[cfg(target_os = "wasi")] {
-        fn get_non_stdlib_asset(path_to_file: &Path) -> std::io::Result<String> {
+        pub(crate) fn get_non_stdlib_asset(path_to_file: &Path) -> std::io::Result<String> {
             std::fs::read_to_string(path_to_file)
         }
     } else {
@@ -183,7 +395,7 @@ cfg_if::cfg_if! {
             fn read_file(path: &str) -> Result<String, JsValue>;
         }
 
-        fn get_non_stdlib_asset(path_to_file: &Path) -> std::io::Result<String> {
+        pub(crate) fn get_non_stdlib_asset(path_to_file: &Path) -> std::io::Result<String> {
             let path_str = path_to_file.to_str().unwrap();
             match read_file(path_str) {
                 Ok(buffer) => Ok(buffer),
@@ -0,0 +1,163 @@
+use fm::FileManager;
+use noirc_errors::{DiagnosticKind, FileDiagnostic};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const DIAGNOSTIC_TYPE: &'static str = r#"
+export type Diagnostic = {
+    message: string,
+    severity: "error" | "warning" | "bug",
+    file_path: string,
+    span: { start: number, end: number },
+    secondary_labels: { message: string, file_path: string, span: { start: number, end: number } }[]
+}
+"#;
+
+#[derive(Serialize, Clone)]
+pub(crate) struct JsSpan {
+    start: u32,
+    end: u32,
+}
+
+#[derive(Serialize, Clone)]
+pub(crate) struct JsSecondaryLabel {
+    message: String,
+    file_path: String,
+    span: JsSpan,
+}
+
+#[derive(Serialize, Clone)]
+pub(crate) struct JsDiagnostic {
+    message: String,
+    severity: String,
+    file_path: String,
+    span: JsSpan,
+    secondary_labels: Vec<JsSecondaryLabel>,
+}
+
+impl JsDiagnostic {
+    fn from_file_diagnostic(file_diagnostic: &FileDiagnostic, file_manager: &FileManager) -> Self {
+        let diagnostic = &file_diagnostic.diagnostic;
+
+        let file_path = |file_id| {
+            file_manager
+                .path(file_id)
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let severity = match diagnostic.kind {
+            DiagnosticKind::Error => "error",
+            DiagnosticKind::Warning => "warning",
+            DiagnosticKind::Bug => "bug",
+        }
+        .to_string();
+
+        let span = diagnostic
+            .secondaries
+            .first()
+            .map(|label| JsSpan {
+                start: label.span.start(),
+                end: label.span.end(),
+            })
+            .unwrap_or(JsSpan { start: 0, end: 0 });
+
+        let secondary_labels = diagnostic
+            .secondaries
+            .iter()
+            .map(|label| JsSecondaryLabel {
+                message: label.message.clone(),
+                file_path: file_path(file_diagnostic.file_id),
+                span: JsSpan {
+                    start: label.span.start(),
+                    end: label.span.end(),
+                },
+            })
+            .collect();
+
+        JsDiagnostic {
+            message: diagnostic.message.clone(),
+            severity,
+            file_path: file_path(file_diagnostic.file_id),
+            span,
+            secondary_labels,
+        }
+    }
+}
+
+/// Converts a batch of compiler diagnostics (errors or warnings) into their JS-facing shape.
+pub(crate) fn to_js_diagnostics(
+    diagnostics: &[FileDiagnostic],
+    file_manager: &FileManager,
+) -> Vec<JsDiagnostic> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| JsDiagnostic::from_file_diagnostic(diagnostic, file_manager))
+        .collect()
+}
+
+#[wasm_bindgen]
+#[derive(Serialize)]
+pub struct JsCompileError {
+    message: String,
+    diagnostics: Vec<JsDiagnostic>,
+}
+
+#[wasm_bindgen]
+impl JsCompileError {
+    /// Construct a plain message error without any structured diagnostics, e.g. for failures
+    /// that don't originate from the compiler's own diagnostic machinery (a malformed
+    /// dependency graph, a panic hook message, and the like).
+    #[wasm_bindgen(constructor)]
+    pub fn new(message: String) -> JsCompileError {
+        JsCompileError {
+            message,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn diagnostics(&self) -> JsValue {
+        <JsValue as gloo_utils::format::JsValueSerdeExt>::from_serde(&self.diagnostics).unwrap()
+    }
+}
+
+impl JsCompileError {
+    /// Build an error from the real compiler diagnostics instead of collapsing them into a
+    /// generic "Failed to compile" string. Prefers the first `Error`-kind diagnostic for the
+    /// top-level message, falling back to the first diagnostic overall (e.g. a bug) only when
+    /// there's no error-severity one, so a warning never displaces a real failure.
+    pub(crate) fn from_diagnostics(
+        diagnostics: &[FileDiagnostic],
+        file_manager: &FileManager,
+    ) -> JsCompileError {
+        let message = diagnostics
+            .iter()
+            .find(|diagnostic| matches!(diagnostic.diagnostic.kind, DiagnosticKind::Error))
+            .or_else(|| diagnostics.first())
+            .map(|diagnostic| diagnostic.diagnostic.message.clone())
+            .unwrap_or_else(|| "Failed to compile".to_string());
+
+        JsCompileError {
+            message,
+            diagnostics: to_js_diagnostics(diagnostics, file_manager),
+        }
+    }
+}
+
+impl From<JsCompileError> for JsValue {
+    fn from(error: JsCompileError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.message);
+        let diagnostics =
+            <JsValue as gloo_utils::format::JsValueSerdeExt>::from_serde(&error.diagnostics)
+                .unwrap();
+        js_sys::Reflect::set(&js_error, &JsValue::from_str("diagnostics"), &diagnostics).unwrap();
+        js_error.into()
+    }
+}
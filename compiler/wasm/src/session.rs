@@ -0,0 +1,209 @@
+use std::path::{Path, PathBuf};
+
+use fm::FileManager;
+use gloo_utils::format::JsValueSerdeExt;
+use js_sys::Map;
+use noirc_driver::{compile_contract, compile_main, prepare_crate, CompileOptions};
+use noirc_frontend::{graph::CrateGraph, hir::Context};
+use wasm_bindgen::prelude::*;
+
+use crate::compile::{
+    add_noir_lib_dependencies, make_file_resolver, make_opcode_support, parse_backend_config,
+    parse_compile_config, parse_dependencies, parse_file_source_map, preprocess_contract,
+    preprocess_program, BackendConfig, CompileConfig, CompileResult, DependencyArray,
+    JsBackendConfig, JsCompileConfig,
+};
+use crate::errors::{to_js_diagnostics, JsCompileError};
+
+/// A long-lived compilation session. Unlike the one-shot `compile` function, a session keeps its
+/// `Context` (and with it the `FileManager` and every prepared dependency `CrateId`) alive across
+/// calls, so stdlib and dependency crates are only ever loaded and prepared once no matter how many
+/// times `compile` is called. This is the shape an editor needs: it can push a new buffer with
+/// `set_file` after every keystroke and call `compile` without paying to rebuild the dependency
+/// graph from scratch each time.
+///
+/// The frontend doesn't support re-elaborating a single crate in isolation, so a `compile` call
+/// after any `set_file` still re-elaborates the whole crate graph; this is a whole-session result
+/// cache, not per-file incremental compilation. What the session avoids is the much larger cost
+/// of rebuilding the `FileManager`/`CrateGraph` and re-running `prepare_crate`/`prepare_dependency`
+/// for every file on every call, and it skips re-elaboration entirely when nothing has changed
+/// since the last `compile` with the same arguments.
+#[wasm_bindgen]
+pub struct CompilationSession {
+    context: Context,
+    root_crate_id: noirc_frontend::graph::CrateId,
+    backend_config: BackendConfig,
+    is_dirty: bool,
+    cached_key: Option<(bool, CompileConfig)>,
+    cached_result: Option<JsValue>,
+}
+
+#[wasm_bindgen]
+impl CompilationSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        entry_point: String,
+        dependencies: Option<DependencyArray>,
+        file_source_map: Option<Map>,
+        backend_config: Option<JsBackendConfig>,
+    ) -> Result<CompilationSession, JsCompileError> {
+        console_error_panic_hook::set_once();
+
+        let root = Path::new("/");
+        let overlay = parse_file_source_map(file_source_map);
+        let fm = FileManager::new(root, make_file_resolver(overlay));
+        let graph = CrateGraph::default();
+        let mut context = Context::new(fm, graph);
+
+        let path = Path::new(&entry_point);
+        let root_crate_id = prepare_crate(&mut context, path);
+
+        let dependencies = parse_dependencies(dependencies);
+        add_noir_lib_dependencies(&mut context, dependencies).map_err(JsCompileError::new)?;
+
+        Ok(CompilationSession {
+            context,
+            root_crate_id,
+            backend_config: parse_backend_config(backend_config),
+            is_dirty: false,
+            cached_key: None,
+            cached_result: None,
+        })
+    }
+
+    /// Pushes in-memory contents for `path` into the session's `FileManager`, taking precedence
+    /// over whatever the file resolver (JS `read_file` or the initial `file_source_map` overlay)
+    /// would otherwise return, and marks the session dirty so the next `compile` call
+    /// re-elaborates instead of returning its cached result.
+    #[wasm_bindgen(js_name = "setFile")]
+    pub fn set_file(&mut self, path: String, contents: String) {
+        let path = PathBuf::from(path);
+        self.context
+            .file_manager
+            .add_file_with_source(&path, contents);
+        self.is_dirty = true;
+        self.cached_key = None;
+        self.cached_result = None;
+    }
+
+    pub fn compile(
+        &mut self,
+        contracts: Option<bool>,
+        options: Option<JsCompileConfig>,
+    ) -> Result<JsValue, JsCompileError> {
+        let contracts = contracts.unwrap_or_default();
+        let compile_config = parse_compile_config(options);
+        let cache_key = (contracts, compile_config.clone());
+
+        if !self.is_dirty && self.cached_key.as_ref() == Some(&cache_key) {
+            if let Some(cached_result) = &self.cached_result {
+                return Ok(cached_result.clone());
+            }
+        }
+
+        let compile_options = CompileOptions::from(&compile_config);
+
+        let np_language = acvm::Language::from(&self.backend_config.language);
+        let is_opcode_supported =
+            make_opcode_support(np_language, &self.backend_config.supported_opcodes);
+
+        let result = if contracts {
+            let (compiled_contract, warnings) =
+                compile_contract(&mut self.context, self.root_crate_id, &compile_options).map_err(
+                    |diagnostics| {
+                        JsCompileError::from_diagnostics(&diagnostics, &self.context.file_manager)
+                    },
+                )?;
+
+            let optimized_contract =
+                nargo::ops::optimize_contract(compiled_contract, np_language, &is_opcode_supported)
+                    .expect("Contract optimization failed");
+
+            let result = CompileResult {
+                program: preprocess_contract(
+                    optimized_contract,
+                    &self.backend_config.backend_identifier,
+                ),
+                warnings: to_js_diagnostics(&warnings, &self.context.file_manager),
+            };
+
+            <JsValue as JsValueSerdeExt>::from_serde(&result).unwrap()
+        } else {
+            let (compiled_program, warnings) = compile_main(
+                &mut self.context,
+                self.root_crate_id,
+                &compile_options,
+                None,
+                true,
+            )
+            .map_err(|diagnostics| {
+                JsCompileError::from_diagnostics(&diagnostics, &self.context.file_manager)
+            })?;
+
+            let optimized_program =
+                nargo::ops::optimize_program(compiled_program, np_language, &is_opcode_supported)
+                    .expect("Program optimization failed");
+
+            let result = CompileResult {
+                program: preprocess_program(
+                    optimized_program,
+                    &self.backend_config.backend_identifier,
+                ),
+                warnings: to_js_diagnostics(&warnings, &self.context.file_manager),
+            };
+
+            <JsValue as JsValueSerdeExt>::from_serde(&result).unwrap()
+        };
+
+        self.is_dirty = false;
+        self.cached_key = Some(cache_key);
+        self.cached_result = Some(result.clone());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY_POINT: &str = "/main.nr";
+
+    fn new_session(source: &str) -> CompilationSession {
+        let overlay = Map::new();
+        overlay.set(&JsValue::from_str(ENTRY_POINT), &JsValue::from_str(source));
+
+        CompilationSession::new(ENTRY_POINT.to_string(), None, Some(overlay), None)
+            .expect("session should construct from a single-file overlay")
+    }
+
+    fn program_json(result: &JsValue) -> String {
+        let value: serde_json::Value = JsValueSerdeExt::into_serde(result).unwrap();
+        value["program"].to_string()
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn set_file_recompiles_with_the_new_source() {
+        let mut session = new_session("fn main(x: Field) { assert(x == 1); }");
+
+        let first = session
+            .compile(Some(false), None)
+            .expect("first compile should succeed");
+        let first_program = program_json(&first);
+
+        session.set_file(
+            ENTRY_POINT.to_string(),
+            "fn main(x: Field) { assert(x == 2); }".to_string(),
+        );
+
+        let second = session
+            .compile(Some(false), None)
+            .expect("recompile after set_file should succeed");
+        let second_program = program_json(&second);
+
+        assert_ne!(
+            first_program, second_program,
+            "compile() after set_file should reflect the edited source, not the cached program \
+             from before the edit"
+        );
+    }
+}